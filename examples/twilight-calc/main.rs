@@ -6,14 +6,16 @@ extern crate serde_derive;
 
 extern crate twilight;
 
+use twilight::Coordinate;
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let location = get_location()?;
-    println!("    location: {}", location);
+    println!("    location: {}", Coordinate::from((location.lat, location.lng)));
 
     let now = chrono::Local::now();
     println!("    time now: {}", now);
 
-    let tw = twilight::Twilight::calculate(now, location.lat, location.lng);
+    let tw = twilight::Twilight::calculate(now, (location.lat, location.lng));
     println!("   day/night: {:?}", tw.state());
 
     match tw.twilight_times() {
@@ -53,12 +55,3 @@ fn get_location() -> Result<Location, Box<dyn std::error::Error>> {
 
     Ok(resp.location)
 }
-
-impl ::std::fmt::Display for Location {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
-        let sign_lat = if self.lat >= 0.0 { "N" } else { "S" };
-        let sign_lng = if self.lng >= 0.0 { "E" } else { "W" };
-
-        write!(f, "({}°{}, {}°{})", self.lat, sign_lat, self.lng, sign_lng)
-    }
-}
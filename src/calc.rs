@@ -1,12 +1,17 @@
-use crate::{State, Twilight, TwilightTimes};
+use crate::{SolarPosition, State, Twilight, TwilightKind, TwilightTimes};
 
 const DEGREES_TO_RADIANS: f64 = ::std::f64::consts::PI / 180.0;
 
 // element for calculating solar transit.
 const J0: f64 = 0.0009;
 
-// correction for civil twilight
+// magnitude, in radians, of how far below the horizon the sun has to be
+// for each `TwilightKind`; negated before being fed into `result_factory`,
+// since the sun is below the horizon during all of these events.
+const ALTITUDE_CORRECTION_GEOMETRIC: f64 = ::std::f64::consts::PI / 180.0 * 0.833;
 const ALTITUDE_CORRECTION_CIVIL_TWILIGHT: f64 = ::std::f64::consts::PI / 180.0 * 6.0;
+const ALTITUDE_CORRECTION_NAUTICAL_TWILIGHT: f64 = ::std::f64::consts::PI / 180.0 * 12.0;
+const ALTITUDE_CORRECTION_ASTRONOMICAL_TWILIGHT: f64 = ::std::f64::consts::PI / 180.0 * 18.0;
 
 // coefficients for calculating Equation of Center.
 const C1: f64 = 0.0334196;
@@ -20,12 +25,56 @@ const UTC_2000: i64 = 946728000000;
 
 const DAY_IN_MILLIS: i64 = 1000 * 60 * 60 * 24;
 
-/// calculates the civil twilight bases on time and geo-coordinates.
+/// calculates the twilight of the given kind, bases on time and geo-coordinates.
 ///
 /// @param time time in milliseconds.
 /// @param latitude latitude in degrees.
 /// @param longitude latitude in degrees.
-pub(crate) fn calculate_twilight(time: i64, latitude: f64, longitude: f64) -> Twilight {
+/// @param kind the twilight band to calculate the rise/set times for.
+/// @param observer_height_m height of the observer above sea level, in meters.
+pub(crate) fn calculate_twilight(
+    time: i64,
+    latitude: f64,
+    longitude: f64,
+    kind: TwilightKind,
+    observer_height_m: f64,
+) -> Twilight {
+    let ephemeris = solar_ephemeris(time, longitude);
+
+    let lat_rad = latitude * DEGREES_TO_RADIANS;
+
+    let result_factory = |sun_altitude_delta: f64| {
+        let cos_hour_angle = (sun_altitude_delta.sin()
+            - lat_rad.sin() * ephemeris.declination.sin())
+            / (lat_rad.cos() * ephemeris.declination.cos());
+
+        cos_hour_angle_to_times(time, ephemeris.solar_transit_j2000, cos_hour_angle)
+    };
+
+    let altitude_correction = match kind {
+        TwilightKind::Geometric => ALTITUDE_CORRECTION_GEOMETRIC,
+        TwilightKind::Civil => ALTITUDE_CORRECTION_CIVIL_TWILIGHT,
+        TwilightKind::Nautical => ALTITUDE_CORRECTION_NAUTICAL_TWILIGHT,
+        TwilightKind::Astronomical => ALTITUDE_CORRECTION_ASTRONOMICAL_TWILIGHT,
+    };
+
+    // The dip of the horizon as seen by an elevated observer lets them see
+    // the sun a bit earlier/later than someone at sea level would, which we
+    // model by requiring the sun to be that much further below the horizon.
+    let horizon_dip = 0.0353 * observer_height_m.sqrt() * DEGREES_TO_RADIANS;
+
+    result_factory(-(altitude_correction + horizon_dip))
+}
+
+// Intermediate results of the solar ephemeris calculation, shared by both
+// the twilight time calculation and the solar position calculation below.
+struct SolarEphemeris {
+    days_since_2000: f64,
+    declination: f64,
+    solar_transit_j2000: f64,
+}
+
+fn solar_ephemeris(time: i64, longitude: f64) -> SolarEphemeris {
     let days_since_2000 = (time - UTC_2000) as f64 / (DAY_IN_MILLIS as f64);
 
     // mean anomaly
@@ -47,33 +96,63 @@ pub(crate) fn calculate_twilight(time: i64, latitude: f64, longitude: f64) -> Tw
         n + J0 + arc_longitude + 0.0053 * mean_anomaly.sin() + -0.0069 * (2.0 * solar_lng).sin();
 
     // declination of sun
-    let solar_dec = (solar_lng.sin() * OBLIQUITY.sin()).asin();
+    let declination = (solar_lng.sin() * OBLIQUITY.sin()).asin();
+
+    SolarEphemeris {
+        days_since_2000,
+        declination,
+        solar_transit_j2000,
+    }
+}
+
+/// calculates the position of the sun (azimuth and elevation) at the given
+/// time, as seen from the given geo-coordinates.
+///
+/// @param time time in milliseconds.
+/// @param latitude latitude in degrees.
+/// @param longitude longitude in degrees.
+pub(crate) fn calculate_solar_position(time: i64, latitude: f64, longitude: f64) -> SolarPosition {
+    let ephemeris = solar_ephemeris(time, longitude);
 
     let lat_rad = latitude * DEGREES_TO_RADIANS;
+    let dec = ephemeris.declination;
 
-    let result_factory = |sun_altitude_delta: f64| {
-        let cos_hour_angle = (sun_altitude_delta.sin()
-            - lat_rad.sin() * solar_dec.sin())
-            / (lat_rad.cos() * solar_dec.cos());
+    // local hour angle: how far past solar transit `time` is, as an angle.
+    let hour_angle =
+        (ephemeris.days_since_2000 - ephemeris.solar_transit_j2000) * 2.0 * ::std::f64::consts::PI;
 
-        cos_hour_angle_to_times(time, solar_transit_j2000, cos_hour_angle)
-    };
+    let elevation =
+        (lat_rad.sin() * dec.sin() + lat_rad.cos() * dec.cos() * hour_angle.cos()).asin();
 
-    result_factory(ALTITUDE_CORRECTION_CIVIL_TWILIGHT)
+    // Measured from south, positive westward (the usual convention for this
+    // formula); rotated below into the more familiar compass bearing.
+    let azimuth_from_south = hour_angle
+        .sin()
+        .atan2(hour_angle.cos() * lat_rad.sin() - dec.tan() * lat_rad.cos());
+    let azimuth = (azimuth_from_south / DEGREES_TO_RADIANS + 180.0).rem_euclid(360.0);
+
+    SolarPosition {
+        azimuth,
+        elevation: elevation / DEGREES_TO_RADIANS,
+    }
 }
 
 fn cos_hour_angle_to_times(time: i64, solar_transit_j2000: f64, cos_hour_angle: f64) -> Twilight {
+    let solar_transit = solar_transit_j2000_to_ms(solar_transit_j2000);
+
     // The day or night never ends for the given date and location, if this value is out of
     // range.
     if cos_hour_angle >= 1.0 {
         return Twilight {
             state: State::Night,
             times: None,
+            solar_transit,
         };
     } else if cos_hour_angle <= -1.0 {
         return Twilight {
             state: State::Day,
             times: None,
+            solar_transit,
         };
     }
 
@@ -88,21 +167,23 @@ fn cos_hour_angle_to_times(time: i64, solar_transit_j2000: f64, cos_hour_angle:
     };
 
     Twilight {
-        state: state,
+        state,
         times: Some(times),
+        solar_transit,
     }
 }
 
+fn solar_transit_j2000_to_ms(solar_transit_j2000: f64) -> i64 {
+    (solar_transit_j2000 * DAY_IN_MILLIS as f64).round() as i64 + UTC_2000
+}
+
 fn hour_angle_to_times(solar_transit_j2000: f64, hour_angle: f64) -> TwilightTimes {
     let sunset =
         ((solar_transit_j2000 + hour_angle) * DAY_IN_MILLIS as f64).round() as i64 + UTC_2000;
     let sunrise =
         ((solar_transit_j2000 - hour_angle) * DAY_IN_MILLIS as f64).round() as i64 + UTC_2000;
 
-    TwilightTimes {
-        sunset: sunset,
-        sunrise: sunrise,
-    }
+    TwilightTimes { sunset, sunrise }
 }
 
 #[cfg(test)]
@@ -153,7 +234,7 @@ mod tests {
                 let (lon_sign, lon_h) = if lon_h < 0 { (-1.0, -lon_h) } else { (1.0, lon_h) };
                 let lon = lon_sign * (lon_h * 3600 + $lon_m * 60 + $lon_s) as f64 / 3600.0;
 
-                let result = calculate_twilight($now, lat, lon);
+                let result = calculate_twilight($now, lat, lon, TwilightKind::Civil, 0.0);
 
                 assert_eq!(result.state, $state);
                 assert_eq!(result.times, $times);
@@ -167,8 +248,8 @@ mod tests {
             @ 1566703808294  // 2019-08-25T11:30:08.294+08:00
             => {
                 State::Day,
-                1566683966997,  // 05:59:26
-                1566726984202,  // 17:56:24
+                1566680508648,  // 05:01:48
+                1566730442552,  // 18:54:02
             }
         );
 
@@ -181,4 +262,116 @@ mod tests {
             }
         );
     }
+
+    // location of Shanghai (People's Square), and the time `it_works` was written.
+    const SHANGHAI_LAT: f64 = 31.0 + 13.0 / 60.0 + 43.0 / 3600.0;
+    const SHANGHAI_LON: f64 = 121.0 + 28.0 / 60.0 + 29.0 / 3600.0;
+    const SHANGHAI_NOW: i64 = 1566703808294;
+
+    #[test]
+    fn twilight_kinds_match_real_solar_elevation() {
+        let cases = [
+            (TwilightKind::Geometric, -0.833),
+            (TwilightKind::Civil, -6.0),
+            (TwilightKind::Nautical, -12.0),
+            (TwilightKind::Astronomical, -18.0),
+        ];
+
+        for (kind, expected_elevation) in cases.iter().copied() {
+            let twilight = calculate_twilight(SHANGHAI_NOW, SHANGHAI_LAT, SHANGHAI_LON, kind, 0.0);
+            let times = twilight.times.expect("no polar day/night at this date/location");
+            let position = calculate_solar_position(times.sunrise, SHANGHAI_LAT, SHANGHAI_LON);
+
+            assert!(
+                (position.elevation - expected_elevation).abs() < 0.1,
+                "{:?}: expected elevation near {}, got {}",
+                kind,
+                expected_elevation,
+                position.elevation
+            );
+        }
+    }
+
+    #[test]
+    fn twilight_kinds_are_ordered_by_band_width() {
+        let times_for = |kind| {
+            calculate_twilight(SHANGHAI_NOW, SHANGHAI_LAT, SHANGHAI_LON, kind, 0.0)
+                .times
+                .expect("no polar day/night at this date/location")
+        };
+
+        let geometric = times_for(TwilightKind::Geometric);
+        let civil = times_for(TwilightKind::Civil);
+        let nautical = times_for(TwilightKind::Nautical);
+        let astronomical = times_for(TwilightKind::Astronomical);
+
+        assert!(astronomical.sunrise < nautical.sunrise);
+        assert!(nautical.sunrise < civil.sunrise);
+        assert!(civil.sunrise < geometric.sunrise);
+
+        assert!(geometric.sunset < civil.sunset);
+        assert!(civil.sunset < nautical.sunset);
+        assert!(nautical.sunset < astronomical.sunset);
+    }
+
+    #[test]
+    fn observer_altitude_widens_the_day() {
+        let sea_level = calculate_twilight(
+            SHANGHAI_NOW,
+            SHANGHAI_LAT,
+            SHANGHAI_LON,
+            TwilightKind::Civil,
+            0.0,
+        )
+        .times
+        .expect("no polar day/night at this date/location");
+
+        let elevated = calculate_twilight(
+            SHANGHAI_NOW,
+            SHANGHAI_LAT,
+            SHANGHAI_LON,
+            TwilightKind::Civil,
+            3000.0,
+        )
+        .times
+        .expect("no polar day/night at this date/location");
+
+        // an elevated observer's horizon dips below the sea-level horizon,
+        // so they should see the sun earlier in the morning and later in
+        // the evening than someone at sea level.
+        assert!(elevated.sunrise < sea_level.sunrise);
+        assert!(elevated.sunset > sea_level.sunset);
+    }
+
+    #[test]
+    fn solar_position_at_solar_noon_faces_south() {
+        // solar transit (noon) on the same day as `it_works`'s first testcase.
+        let solar_noon = 1566705475600;
+
+        let position = calculate_solar_position(solar_noon, SHANGHAI_LAT, SHANGHAI_LON);
+
+        assert!((position.azimuth - 180.0).abs() < 1.0);
+        assert!((position.elevation - 69.77).abs() < 0.5);
+    }
+
+    #[test]
+    fn day_length_matches_sunrise_to_sunset() {
+        let twilight = calculate_twilight(
+            SHANGHAI_NOW,
+            SHANGHAI_LAT,
+            SHANGHAI_LON,
+            TwilightKind::Civil,
+            0.0,
+        );
+        let times = twilight.times.expect("no polar day/night at this date/location");
+
+        // with the sun required to be below the horizon, civil dawn to
+        // civil dusk in late-summer Shanghai spans a little under 14 hours.
+        let hours = twilight.day_length().num_minutes() as f64 / 60.0;
+        assert!((13.8..14.2).contains(&hours), "day_length: {}h", hours);
+        assert_eq!(
+            twilight.day_length(),
+            ::chrono::Duration::milliseconds(times.sunset - times.sunrise)
+        );
+    }
 }
@@ -0,0 +1,170 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// A geographic coordinate, in degrees.
+///
+/// Implements [`FromStr`] so it can be parsed from sexagesimal notation
+/// like `31°13'43"N, 121°28'29"E`, as well as from bare decimal degrees
+/// like `31.228611, 121.474722`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Coordinate {
+    /// Latitude, in degrees. Positive is north, negative is south.
+    pub latitude: f64,
+    /// Longitude, in degrees. Positive is east, negative is west.
+    pub longitude: f64,
+}
+
+impl From<(f64, f64)> for Coordinate {
+    /// Converts a `(latitude, longitude)` pair, in degrees, into a `Coordinate`.
+    fn from((latitude, longitude): (f64, f64)) -> Self {
+        Coordinate { latitude, longitude }
+    }
+}
+
+impl FromStr for Coordinate {
+    type Err = ParseCoordinateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, ',');
+        let latitude = parts.next().ok_or_else(|| ParseCoordinateError::new(s))?;
+        let longitude = parts.next().ok_or_else(|| ParseCoordinateError::new(s))?;
+
+        Ok(Coordinate {
+            latitude: parse_component(latitude)?,
+            longitude: parse_component(longitude)?,
+        })
+    }
+}
+
+impl fmt::Display for Coordinate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lat_hemisphere = if self.latitude >= 0.0 { "N" } else { "S" };
+        let lng_hemisphere = if self.longitude >= 0.0 { "E" } else { "W" };
+
+        write!(
+            f,
+            "{}°{}, {}°{}",
+            self.latitude.abs(),
+            lat_hemisphere,
+            self.longitude.abs(),
+            lng_hemisphere
+        )
+    }
+}
+
+// Parses one half of a coordinate pair, e.g. `31°13'43"N` or `-31.5`.
+fn parse_component(s: &str) -> Result<f64, ParseCoordinateError> {
+    let s = s.trim();
+    let last = s.chars().next_back().ok_or_else(|| ParseCoordinateError::new(s))?;
+
+    let (magnitude, hemisphere) = if last.is_ascii_alphabetic() {
+        (s[..s.len() - last.len_utf8()].trim(), Some(last.to_ascii_uppercase()))
+    } else {
+        (s, None)
+    };
+
+    let value = parse_dms(magnitude, s)?;
+
+    // A magnitude that's already negative and a hemisphere letter are two
+    // ways of saying the same thing; accepting both would silently flip
+    // the sign back, e.g. "-31.5S" would parse as +31.5.
+    if hemisphere.is_some() && value.is_sign_negative() {
+        return Err(ParseCoordinateError::new(s));
+    }
+
+    match hemisphere {
+        None | Some('N') | Some('E') => Ok(value),
+        Some('S') | Some('W') => Ok(-value),
+        Some(_) => Err(ParseCoordinateError::new(s)),
+    }
+}
+
+// Parses a signed degrees[°minutes['seconds]] magnitude into decimal degrees.
+fn parse_dms(s: &str, original: &str) -> Result<f64, ParseCoordinateError> {
+    let components: Vec<&str> = s
+        .split(['°', '\'', '′', '"', '″'])
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .collect();
+
+    if components.is_empty() || components.len() > 3 {
+        return Err(ParseCoordinateError::new(original));
+    }
+
+    let parse_f64 = |c: &str| {
+        c.parse::<f64>()
+            .map_err(|_| ParseCoordinateError::new(original))
+    };
+
+    let degrees: f64 = parse_f64(components[0])?;
+    let sign = if degrees.is_sign_negative() { -1.0 } else { 1.0 };
+
+    let minutes = match components.get(1) {
+        Some(c) => parse_f64(c)?,
+        None => 0.0,
+    };
+    let seconds = match components.get(2) {
+        Some(c) => parse_f64(c)?,
+        None => 0.0,
+    };
+
+    Ok(sign * (degrees.abs() + minutes / 60.0 + seconds / 3600.0))
+}
+
+/// Error returned when parsing a [`Coordinate`] from a string fails.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ParseCoordinateError {
+    input: String,
+}
+
+impl ParseCoordinateError {
+    fn new(input: &str) -> Self {
+        ParseCoordinateError {
+            input: input.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseCoordinateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid coordinate: {:?}", self.input)
+    }
+}
+
+impl ::std::error::Error for ParseCoordinateError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_dms_with_hemisphere_letters() {
+        let c: Coordinate = "31°13'43\"N, 121°28'29\"E".parse().unwrap();
+        assert!((c.latitude - (31.0 + 13.0 / 60.0 + 43.0 / 3600.0)).abs() < 1e-9);
+        assert!((c.longitude - (121.0 + 28.0 / 60.0 + 29.0 / 3600.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn parses_bare_decimal_degrees() {
+        let c: Coordinate = "31.228611, 121.474722".parse().unwrap();
+        assert_eq!(c.latitude, 31.228611);
+        assert_eq!(c.longitude, 121.474722);
+    }
+
+    #[test]
+    fn south_and_west_flip_the_sign() {
+        let c: Coordinate = "73°51'50\"S, 76°58'29\"W".parse().unwrap();
+        assert!(c.latitude < 0.0);
+        assert!(c.longitude < 0.0);
+    }
+
+    #[test]
+    fn rejects_a_signed_magnitude_combined_with_a_hemisphere_letter() {
+        assert!("-31.5S, 121.0E".parse::<Coordinate>().is_err());
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!("not a coordinate".parse::<Coordinate>().is_err());
+    }
+}
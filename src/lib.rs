@@ -12,6 +12,9 @@
 #![deny(missing_docs, warnings)]
 
 mod calc;
+mod coordinate;
+
+pub use crate::coordinate::{Coordinate, ParseCoordinateError};
 
 /// State of day.
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
@@ -22,6 +25,91 @@ pub enum State {
     Night,
 }
 
+/// Polar day/night status of a location that doesn't see the sun cross a
+/// given twilight band's altitude threshold within a day.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum PolarState {
+    /// The sun never goes below the threshold: polar day.
+    PolarDay,
+    /// The sun never goes above the threshold: polar night.
+    PolarNight,
+}
+
+/// The various bands of twilight, identified by how far below the
+/// horizon the sun has to be for the period to still count as such.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum TwilightKind {
+    /// The geometric horizon, accounting for the ~0.833° of atmospheric
+    /// refraction; i.e. the classic sunrise/sunset.
+    Geometric,
+    /// Civil twilight: enough light for most outdoor activities without
+    /// additional illumination.
+    Civil,
+    /// Nautical twilight: the horizon is still visible at sea, and the
+    /// brighter stars can be used for navigation.
+    Nautical,
+    /// Astronomical twilight: the sky is dark enough for astronomical
+    /// observation, save for the last vestiges of scattered sunlight.
+    Astronomical,
+}
+
+/// The position of the sun in the sky at a given instant, as seen from a
+/// given location.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SolarPosition {
+    /// Azimuth of the sun, in degrees clockwise from north (0 = north,
+    /// 90 = east, 180 = south, 270 = west).
+    pub azimuth: f64,
+    /// Elevation of the sun above the horizon, in degrees. Negative when
+    /// the sun is below the horizon.
+    pub elevation: f64,
+}
+
+/// Calculates the position of the sun for a given time and location.
+pub fn solar_position<T: Timestamp, C: Into<Coordinate>>(
+    time_of_day: T,
+    location: C,
+) -> SolarPosition {
+    let ms = time_of_day.as_unix_timestamp_ms();
+    let location = location.into();
+    calc::calculate_solar_position(ms, location.latitude, location.longitude)
+}
+
+/// Coarse classification of where the sun currently is, suitable for e.g.
+/// coloring a clock face or map by the sun's current phase.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Band {
+    /// The sun is above the horizon.
+    Day,
+    /// The sun is below the horizon, within civil twilight (down to -6°).
+    Civil,
+    /// The sun is below the horizon, within nautical twilight (down to -12°).
+    Nautical,
+    /// The sun is below the horizon, within astronomical twilight (down to -18°).
+    Astronomical,
+    /// The sun is below the horizon, past astronomical twilight.
+    Night,
+}
+
+/// Classifies the sun's current band (day/civil/nautical/astronomical/night)
+/// for a given time and location, without needing several separate
+/// `Twilight::calculate_with` calls at different `TwilightKind`s.
+pub fn solar_band<T: Timestamp, C: Into<Coordinate>>(time_of_day: T, location: C) -> Band {
+    let elevation = solar_position(time_of_day, location).elevation;
+
+    if elevation > 0.0 {
+        Band::Day
+    } else if elevation > -6.0 {
+        Band::Civil
+    } else if elevation > -12.0 {
+        Band::Nautical
+    } else if elevation > -18.0 {
+        Band::Astronomical
+    } else {
+        Band::Night
+    }
+}
+
 /// Twilight times of a given day.
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct TwilightTimes {
@@ -47,20 +135,69 @@ impl TwilightTimes {
 pub struct Twilight {
     state: State,
     times: Option<TwilightTimes>,
+    solar_transit: i64,
 }
 
 impl Twilight {
     /// Calculates civil twilight times for a given time and location.
-    pub fn calculate<T: Timestamp>(time_of_day: T, latitude: f64, longitude: f64) -> Self {
+    ///
+    /// `location` accepts anything convertible into a [`Coordinate`], so
+    /// callers can pass a `(latitude, longitude)` pair, a `Coordinate`, or
+    /// a sexagesimal string parsed through `Coordinate`'s `FromStr` impl.
+    pub fn calculate<T: Timestamp, C: Into<Coordinate>>(time_of_day: T, location: C) -> Self {
+        Self::calculate_with(time_of_day, location, TwilightKind::Civil)
+    }
+
+    /// Calculates twilight times of the given `kind` for a given time and
+    /// location, allowing callers to pick the geometric, civil, nautical
+    /// or astronomical band instead of being stuck with civil twilight.
+    pub fn calculate_with<T: Timestamp, C: Into<Coordinate>>(
+        time_of_day: T,
+        location: C,
+        kind: TwilightKind,
+    ) -> Self {
+        Self::calculate_with_altitude(time_of_day, location, kind, 0.0)
+    }
+
+    /// Calculates civil twilight times for a given time and location, taking
+    /// the observer's height above sea level into account. This is useful
+    /// for e.g. mountain-top or aircraft sunrise/sunset, where the horizon
+    /// dips below the sea-level horizon and the sun becomes visible earlier
+    /// and stays visible later than the `calculate` default assumes.
+    pub fn calculate_at_altitude<T: Timestamp, C: Into<Coordinate>>(
+        time_of_day: T,
+        location: C,
+        observer_height_m: f64,
+    ) -> Self {
+        Self::calculate_with_altitude(time_of_day, location, TwilightKind::Civil, observer_height_m)
+    }
+
+    /// Calculates twilight times of the given `kind`, taking the observer's
+    /// height above sea level into account. See [`Twilight::calculate_with`]
+    /// and [`Twilight::calculate_at_altitude`] for the individual knobs this
+    /// combines.
+    pub fn calculate_with_altitude<T: Timestamp, C: Into<Coordinate>>(
+        time_of_day: T,
+        location: C,
+        kind: TwilightKind,
+        observer_height_m: f64,
+    ) -> Self {
         let ms = time_of_day.as_unix_timestamp_ms();
-        calc::calculate_twilight(ms, latitude, longitude)
+        let location = location.into();
+        calc::calculate_twilight(
+            ms,
+            location.latitude,
+            location.longitude,
+            kind,
+            observer_height_m,
+        )
     }
 
     /// Convenient method for calculating civil twilight times with the
     /// current time, for a given location.
-    pub fn now(latitude: f64, longitude: f64) -> Self {
+    pub fn now<C: Into<Coordinate>>(location: C) -> Self {
         let time_of_day = ::chrono::Utc::now();
-        Self::calculate(time_of_day, latitude, longitude)
+        Self::calculate(time_of_day, location)
     }
 
     /// Returns if the specified time is day or night at the specified location.
@@ -73,11 +210,45 @@ impl Twilight {
     pub fn twilight_times(&self) -> Option<TwilightTimes> {
         self.times
     }
+
+    /// Returns whether the specified location is under polar day or polar
+    /// night for the requested `TwilightKind`, i.e. the sun never crosses
+    /// that band's altitude threshold within the given day. `None` means
+    /// an ordinary sunrise/sunset happened, and [`Twilight::twilight_times`]
+    /// can be used to get at it.
+    pub fn polar_state(&self) -> Option<PolarState> {
+        if self.times.is_some() {
+            return None;
+        }
+
+        match self.state {
+            State::Day => Some(PolarState::PolarDay),
+            State::Night => Some(PolarState::PolarNight),
+        }
+    }
+
+    /// Time of solar noon (solar transit) in the given day.
+    pub fn solar_noon_time<Tz: ::chrono::TimeZone>(&self, tz: Tz) -> ::chrono::DateTime<Tz> {
+        let (s, ns) = ms_to_s_ns(self.solar_transit);
+        tz.timestamp(s, ns)
+    }
+
+    /// Length of daylight for the given day. Under polar day/night, this is
+    /// a full 24 hours or zero, respectively.
+    pub fn day_length(&self) -> ::chrono::Duration {
+        match self.times {
+            Some(times) => ::chrono::Duration::milliseconds(times.sunset - times.sunrise),
+            None => match self.state {
+                State::Day => ::chrono::Duration::days(1),
+                State::Night => ::chrono::Duration::zero(),
+            },
+        }
+    }
 }
 
 // Converts from millisecond timestamp to (second, nanosecond) format.
 fn ms_to_s_ns(ms: i64) -> (i64, u32) {
-    (ms / 1000, (ms % 1000) as u32 * 1000_000)
+    (ms / 1000, (ms % 1000) as u32 * 1_000_000)
 }
 
 /// Timestamp suitable for this library's consumption.
@@ -91,3 +262,43 @@ impl<Tz: ::chrono::TimeZone> Timestamp for ::chrono::DateTime<Tz> {
         self.timestamp_millis()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    // same fixtures as calc.rs's `it_works` test.
+    const SHANGHAI: (f64, f64) = (
+        31.0 + 13.0 / 60.0 + 43.0 / 3600.0,
+        121.0 + 28.0 / 60.0 + 29.0 / 3600.0,
+    );
+    const SHANGHAI_NOW: i64 = 1566703808294;
+
+    const TAISHAN: (f64, f64) = (
+        -(73.0 + 51.0 / 60.0 + 50.0 / 3600.0),
+        76.0 + 58.0 / 60.0 + 29.0 / 3600.0,
+    );
+    const TAISHAN_NOW: i64 = 1546272000000;
+
+    #[test]
+    fn solar_band_is_day_at_shanghai_midday() {
+        let now = Utc.timestamp_millis(SHANGHAI_NOW);
+        assert_eq!(solar_band(now, SHANGHAI), Band::Day);
+    }
+
+    #[test]
+    fn polar_state_is_none_at_shanghai_midday() {
+        let now = Utc.timestamp_millis(SHANGHAI_NOW);
+        assert_eq!(Twilight::calculate(now, SHANGHAI).polar_state(), None);
+    }
+
+    #[test]
+    fn polar_state_is_polar_day_at_taishan() {
+        let now = Utc.timestamp_millis(TAISHAN_NOW);
+        assert_eq!(
+            Twilight::calculate(now, TAISHAN).polar_state(),
+            Some(PolarState::PolarDay)
+        );
+    }
+}